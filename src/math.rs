@@ -0,0 +1,138 @@
+//! A small 3D linear-algebra module: just the `Vec3` and `Mat3` operations the
+//! renderer needs to build and apply its rotation pipeline.
+
+/// A 3D vector.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vec3 {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn dot(self, other: Vec3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    // rounds out the vector API; not every rotation path needs it.
+    #[allow(dead_code)]
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalized(self) -> Vec3 {
+        let len = self.length();
+        if len == 0.0 {
+            self
+        } else {
+            Vec3::new(self.x / len, self.y / len, self.z / len)
+        }
+    }
+}
+
+impl From<(f64, f64, f64)> for Vec3 {
+    fn from((x, y, z): (f64, f64, f64)) -> Self {
+        Vec3::new(x, y, z)
+    }
+}
+
+/// A 3x3 matrix, stored row-major.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat3 {
+    pub rows: [[f64; 3]; 3],
+}
+
+impl Mat3 {
+    #[allow(dead_code)]
+    pub fn identity() -> Mat3 {
+        Mat3 {
+            rows: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    /// Rotation about the x-axis by `angle` radians.
+    pub fn rot_x(angle: f64) -> Mat3 {
+        let (s, c) = angle.sin_cos();
+        Mat3 {
+            rows: [[1.0, 0.0, 0.0], [0.0, c, -s], [0.0, s, c]],
+        }
+    }
+
+    /// Rotation about the y-axis by `angle` radians.
+    pub fn rot_y(angle: f64) -> Mat3 {
+        let (s, c) = angle.sin_cos();
+        Mat3 {
+            rows: [[c, 0.0, s], [0.0, 1.0, 0.0], [-s, 0.0, c]],
+        }
+    }
+
+    /// Rotation about the z-axis by `angle` radians.
+    pub fn rot_z(angle: f64) -> Mat3 {
+        let (s, c) = angle.sin_cos();
+        Mat3 {
+            rows: [[c, -s, 0.0], [s, c, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    /// Rotation by `angle` radians about an arbitrary `axis`, via Rodrigues'
+    /// formula.  The axis is normalised first, so it need not be a unit vector.
+    pub fn from_axis_angle(axis: Vec3, angle: f64) -> Mat3 {
+        let k = axis.normalized();
+        let (s, c) = angle.sin_cos();
+        let t = 1.0 - c;
+        Mat3 {
+            rows: [
+                [
+                    t * k.x * k.x + c,
+                    t * k.x * k.y - s * k.z,
+                    t * k.x * k.z + s * k.y,
+                ],
+                [
+                    t * k.x * k.y + s * k.z,
+                    t * k.y * k.y + c,
+                    t * k.y * k.z - s * k.x,
+                ],
+                [
+                    t * k.x * k.z - s * k.y,
+                    t * k.y * k.z + s * k.x,
+                    t * k.z * k.z + c,
+                ],
+            ],
+        }
+    }
+
+    /// Apply this matrix to a vector.
+    pub fn apply(&self, v: Vec3) -> Vec3 {
+        Vec3::new(
+            self.rows[0][0] * v.x + self.rows[0][1] * v.y + self.rows[0][2] * v.z,
+            self.rows[1][0] * v.x + self.rows[1][1] * v.y + self.rows[1][2] * v.z,
+            self.rows[2][0] * v.x + self.rows[2][1] * v.y + self.rows[2][2] * v.z,
+        )
+    }
+}
+
+impl std::ops::Mul for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, rhs: Mat3) -> Mat3 {
+        let mut rows = [[0.0; 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| self.rows[i][k] * rhs.rows[k][j]).sum();
+            }
+        }
+        Mat3 { rows }
+    }
+}
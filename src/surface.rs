@@ -0,0 +1,197 @@
+//! Parametric surfaces the renderer can sweep and shade.
+//!
+//! The renderer is agnostic to the shape: it walks the `(u, v)` parameter grid
+//! a [`Surface`] exposes, asks for the untransformed point and normal at each
+//! sample, applies the animation's rotation, projects, and shades from the
+//! rotated normal.  Swapping the torus for a sphere or a knot is therefore just
+//! a matter of picking a different implementor.
+
+use std::f64::consts::PI;
+
+// A bare 3D vector.  The renderer's richer `Vec3`/`Mat3` live in `math`;
+// surfaces only need a handful of operations.
+type V3 = (f64, f64, f64);
+
+fn dot(a: V3, b: V3) -> f64 {
+    a.0 * b.0 + a.1 * b.1 + a.2 * b.2
+}
+
+fn cross(a: V3, b: V3) -> V3 {
+    (
+        a.1 * b.2 - a.2 * b.1,
+        a.2 * b.0 - a.0 * b.2,
+        a.0 * b.1 - a.1 * b.0,
+    )
+}
+
+fn normalize(v: V3) -> V3 {
+    let len = dot(v, v).sqrt();
+    if len == 0.0 {
+        v
+    } else {
+        (v.0 / len, v.1 / len, v.2 / len)
+    }
+}
+
+/// A parametric surface.
+///
+/// For parameters `(u, v)` it yields the untransformed point `(x, y, z)` and
+/// the unit surface normal there.  It also declares the half-open parameter
+/// ranges and sampling spacings the renderer sweeps over.
+pub trait Surface {
+    /// Point and unit normal at `(u, v)`.
+    fn sample(&self, u: f64, v: f64) -> (V3, V3);
+
+    /// Half-open range `[start, end)` swept for `u`.
+    fn u_range(&self) -> (f64, f64);
+
+    /// Half-open range `[start, end)` swept for `v`.
+    fn v_range(&self) -> (f64, f64);
+
+    /// Sampling steps for `u` and `v`.
+    fn spacing(&self) -> (f64, f64);
+}
+
+/// The classic torus: `u` runs around the cross-sectional circle, `v` around
+/// the centre of revolution.
+pub struct Torus {
+    pub r1: f64,
+    pub r2: f64,
+    pub theta_spacing: f64,
+    pub phi_spacing: f64,
+}
+
+impl Surface for Torus {
+    fn sample(&self, u: f64, v: f64) -> (V3, V3) {
+        let (cos_u, sin_u) = (u.cos(), u.sin());
+        let (cos_v, sin_v) = (v.cos(), v.sin());
+
+        // the circle, before revolving
+        let cx = self.r2 + self.r1 * cos_u;
+        let cy = self.r1 * sin_u;
+
+        let point = (cx * cos_v, cy, cx * sin_v);
+        // the cross-section normal (cos_u, sin_u, 0) revolved about the axis
+        let normal = (cos_u * cos_v, sin_u, cos_u * sin_v);
+        (point, normal)
+    }
+
+    fn u_range(&self) -> (f64, f64) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn v_range(&self) -> (f64, f64) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn spacing(&self) -> (f64, f64) {
+        (self.theta_spacing, self.phi_spacing)
+    }
+}
+
+/// A sphere: `u` is the polar angle, `v` the azimuth.
+pub struct Sphere {
+    pub radius: f64,
+    pub u_spacing: f64,
+    pub v_spacing: f64,
+}
+
+impl Surface for Sphere {
+    fn sample(&self, u: f64, v: f64) -> (V3, V3) {
+        let (sin_u, cos_u) = (u.sin(), u.cos());
+        let (cos_v, sin_v) = (v.cos(), v.sin());
+
+        let normal = (sin_u * cos_v, cos_u, sin_u * sin_v);
+        let point = (
+            self.radius * normal.0,
+            self.radius * normal.1,
+            self.radius * normal.2,
+        );
+        (point, normal)
+    }
+
+    fn u_range(&self) -> (f64, f64) {
+        (0.0, PI)
+    }
+
+    fn v_range(&self) -> (f64, f64) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn spacing(&self) -> (f64, f64) {
+        (self.u_spacing, self.v_spacing)
+    }
+}
+
+/// A trefoil knot rendered as a tube: `v` runs along the knot curve, `u` around
+/// the tube cross-section.  The cross-section frame is built from the curve's
+/// tangent and (unnormalised) second derivative, à la Frenet.
+pub struct TrefoilKnot {
+    pub scale: f64,
+    pub tube: f64,
+    pub u_spacing: f64,
+    pub v_spacing: f64,
+}
+
+impl TrefoilKnot {
+    // the knot curve and its first two derivatives at parameter `v`.
+    fn curve(&self, v: f64) -> (V3, V3, V3) {
+        let c = (
+            v.sin() + 2.0 * (2.0 * v).sin(),
+            v.cos() - 2.0 * (2.0 * v).cos(),
+            -(3.0 * v).sin(),
+        );
+        let d1 = (
+            v.cos() + 4.0 * (2.0 * v).cos(),
+            -v.sin() + 4.0 * (2.0 * v).sin(),
+            -3.0 * (3.0 * v).cos(),
+        );
+        let d2 = (
+            -v.sin() - 8.0 * (2.0 * v).sin(),
+            -v.cos() + 8.0 * (2.0 * v).cos(),
+            9.0 * (3.0 * v).sin(),
+        );
+        (c, d1, d2)
+    }
+}
+
+impl Surface for TrefoilKnot {
+    fn sample(&self, u: f64, v: f64) -> (V3, V3) {
+        let (c, d1, d2) = self.curve(v);
+
+        // Frenet-ish frame: tangent, principal normal, binormal.
+        let t = normalize(d1);
+        let n = normalize((
+            d2.0 - dot(d2, t) * t.0,
+            d2.1 - dot(d2, t) * t.1,
+            d2.2 - dot(d2, t) * t.2,
+        ));
+        let bn = cross(t, n);
+
+        let (cos_u, sin_u) = (u.cos(), u.sin());
+        // outward normal of the tube at angle `u`
+        let normal = (
+            cos_u * n.0 + sin_u * bn.0,
+            cos_u * n.1 + sin_u * bn.1,
+            cos_u * n.2 + sin_u * bn.2,
+        );
+        let point = (
+            self.scale * c.0 + self.tube * normal.0,
+            self.scale * c.1 + self.tube * normal.1,
+            self.scale * c.2 + self.tube * normal.2,
+        );
+        (point, normal)
+    }
+
+    fn u_range(&self) -> (f64, f64) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn v_range(&self) -> (f64, f64) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn spacing(&self) -> (f64, f64) {
+        (self.u_spacing, self.v_spacing)
+    }
+}